@@ -1,8 +1,26 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
 use egui::{Align, Key};
 use serde::{Deserialize, Serialize};
 
 use crate::theme::{set_theme, LATTE, MACCHIATO};
 
+/// Directory and file names that are always skipped when walking a project
+/// root, regardless of what the project's `.gitignore` says.
+const DEFAULT_EXCLUDES: &[&str] = &["target", ".git", "node_modules"];
+
+/// Indices into `Project::layers`, which is always seeded with four layers in
+/// this order ("dependencies" and "vector-DB matches" are placeholders for
+/// now: nothing assigns those indices until edge detection and vector search
+/// land, but they're already toggleable in the layers panel).
+const LAYER_SOURCE_FILES: usize = 0;
+const LAYER_TESTS: usize = 1;
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct NapkinService {
     host: String,
@@ -33,6 +51,371 @@ pub enum Theme {
     Dark,
 }
 
+/// The parsed codebase and everything derived from it (indexed files, the
+/// code graph, embeddings, …). Saved to its own file on disk, independent of
+/// the egui app-state blob, so projects are diff-able and portable between
+/// machines.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Project {
+    pub root: Option<PathBuf>,
+    pub indexed_files: Vec<PathBuf>,
+    /// Re-walked from `root` after every load; never serialized since it's
+    /// fully derived from whatever is on disk.
+    #[serde(skip)]
+    pub file_tree: Option<FileNode>,
+    pub graph: CodeGraph,
+    pub layers: Vec<Layer>,
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Self {
+            root: None,
+            indexed_files: Vec::new(),
+            file_tree: None,
+            graph: CodeGraph::default(),
+            layers: default_layers(),
+        }
+    }
+}
+
+impl Project {
+    pub fn new(root: PathBuf) -> Self {
+        let mut project = Self {
+            root: Some(root),
+            indexed_files: Vec::new(),
+            file_tree: None,
+            graph: CodeGraph::default(),
+            layers: default_layers(),
+        };
+        project.rebuild_file_tree();
+        project
+    }
+
+    /// Walks `root` again, applying the default excludes plus a best-effort
+    /// read of its top-level `.gitignore`.
+    pub fn rebuild_file_tree(&mut self) {
+        self.file_tree = self
+            .root
+            .as_ref()
+            .map(|root| walk_dir(root, &gitignore_excludes(root)));
+    }
+
+    /// Regenerates the graph's node list from `indexed_files`. Dependency
+    /// edges between them are left for a later pass once the codebase is
+    /// actually parsed; for now the graph is nodes-only.
+    pub fn rebuild_graph(&mut self) {
+        let previous_positions: HashMap<PathBuf, (f32, f32)> = self
+            .graph
+            .nodes
+            .drain(..)
+            .map(|node| (node.path, (node.x, node.y)))
+            .collect();
+
+        self.graph.nodes = self
+            .indexed_files
+            .iter()
+            .map(|path| {
+                let (x, y) = previous_positions.get(path).copied().unwrap_or((0.0, 0.0));
+                GraphNode {
+                    label: path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    kind: NodeKind::File,
+                    path: path.clone(),
+                    layer: guess_layer_for_path(path),
+                    x,
+                    y,
+                }
+            })
+            .collect();
+        self.graph.edges.clear();
+    }
+
+    pub fn save_to(&self, path: &std::path::Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+    }
+
+    pub fn load_from(path: &std::path::Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        let mut project: Self = serde_json::from_str(&json).map_err(io::Error::from)?;
+        project.rebuild_file_tree();
+        Ok(project)
+    }
+}
+
+/// A single entry in a project's directory tree.
+#[derive(Clone)]
+pub struct FileNode {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub children: Vec<FileNode>,
+    /// Index into `Project::layers`.
+    pub layer: usize,
+}
+
+/// Recursively walks `path`, skipping anything named in `excludes`. Entries
+/// are returned directories-first, then alphabetically.
+fn walk_dir(path: &Path, excludes: &HashSet<String>) -> FileNode {
+    let mut children = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(path) {
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if excludes.contains(&name) {
+                continue;
+            }
+
+            let entry_path = entry.path();
+            if entry_path.is_dir() {
+                children.push(walk_dir(&entry_path, excludes));
+            } else {
+                let layer = guess_layer_for_path(&entry_path);
+                children.push(FileNode {
+                    path: entry_path,
+                    is_dir: false,
+                    children: Vec::new(),
+                    layer,
+                });
+            }
+        }
+
+        children.sort_by_key(|node| !node.is_dir);
+    }
+
+    FileNode {
+        path: path.to_path_buf(),
+        is_dir: true,
+        children,
+        layer: LAYER_SOURCE_FILES,
+    }
+}
+
+/// Combines `DEFAULT_EXCLUDES` with the plain filename patterns found in the
+/// project root's `.gitignore`, if any. This is a best-effort reading of
+/// gitignore syntax (bare names only), not a full implementation.
+fn gitignore_excludes(root: &Path) -> HashSet<String> {
+    let mut excludes: HashSet<String> = DEFAULT_EXCLUDES.iter().map(|s| s.to_string()).collect();
+
+    if let Ok(contents) = fs::read_to_string(root.join(".gitignore")) {
+        for line in contents.lines() {
+            let line = line.trim().trim_end_matches('/');
+            if line.is_empty() || line.starts_with('#') || line.contains('/') || line.contains('*')
+            {
+                continue;
+            }
+            excludes.insert(line.to_string());
+        }
+    }
+
+    excludes
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum NodeKind {
+    File,
+    Module,
+    Symbol,
+}
+
+/// A code entity in the project's dependency graph. `x`/`y` are the node's
+/// last-settled layout position, kept around so the graph doesn't jump on
+/// every reload.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub label: String,
+    pub kind: NodeKind,
+    /// The indexed file this node represents, used both to match it back up
+    /// across a `rebuild_graph` and as prompt context when selected. Defaults
+    /// to empty so project files saved before this field existed still load.
+    #[serde(default)]
+    pub path: PathBuf,
+    /// Index into `Project::layers`.
+    pub layer: usize,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A dependency edge between two `CodeGraph::nodes` indices.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: usize,
+    pub to: usize,
+    /// Index into `Project::layers`.
+    pub layer: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct CodeGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// A named, toggleable grouping for graph nodes/edges and file-browser
+/// entries, so a large codebase can be decluttered a slice at a time.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Layer {
+    pub name: String,
+    pub visible: bool,
+    pub color: [u8; 3],
+}
+
+fn default_layers() -> Vec<Layer> {
+    vec![
+        Layer {
+            name: "source files".to_owned(),
+            visible: true,
+            color: [137, 180, 250],
+        },
+        Layer {
+            name: "tests".to_owned(),
+            visible: true,
+            color: [166, 227, 161],
+        },
+        Layer {
+            name: "dependencies".to_owned(),
+            visible: true,
+            color: [249, 226, 175],
+        },
+        Layer {
+            name: "vector-DB matches".to_owned(),
+            visible: true,
+            color: [203, 166, 247],
+        },
+    ]
+}
+
+/// Buckets a path into "tests" or "source files" by looking for a `test(s)`
+/// path component or filename suffix.
+fn guess_layer_for_path(path: &Path) -> usize {
+    let is_test = path.components().any(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        name == "test" || name == "tests" || name.ends_with("_test") || name.ends_with("_tests")
+    });
+    let stem_is_test = path.file_stem().is_some_and(|stem| {
+        let stem = stem.to_string_lossy();
+        stem.ends_with("_test") || stem.ends_with("_tests")
+    });
+
+    if is_test || stem_is_test {
+        LAYER_TESTS
+    } else {
+        LAYER_SOURCE_FILES
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum ChatRole {
+    User,
+    Assistant,
+}
+
+#[derive(Clone)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+/// One line of Ollama's newline-delimited streaming response.
+#[derive(Deserialize)]
+struct OllamaChunk {
+    response: String,
+    #[serde(default)]
+    done: bool,
+}
+
+#[derive(Serialize)]
+struct OllamaRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    stream: bool,
+}
+
+/// Sent from the background request thread back to the UI thread.
+enum OllamaEvent {
+    Token(String),
+    Done,
+    Error(String),
+}
+
+/// Posts `prompt` to the configured Ollama instance and streams the response
+/// back over an `mpsc` channel so the caller can keep polling it from
+/// `eframe::App::update` without blocking the UI thread.
+fn spawn_ollama_request(
+    service: NapkinService,
+    model: String,
+    prompt: String,
+) -> mpsc::Receiver<OllamaEvent> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let url = format!("http://{}:{}/api/generate", service.host, service.port);
+        let body = OllamaRequest {
+            model: &model,
+            prompt: &prompt,
+            stream: true,
+        };
+
+        let response = match ureq::post(&url).send_json(&body) {
+            Ok(response) => response,
+            Err(err) => {
+                let _ = tx.send(OllamaEvent::Error(err.to_string()));
+                return;
+            }
+        };
+
+        let reader = BufReader::new(response.into_reader());
+        let mut completed = false;
+
+        for line in reader.lines() {
+            let line = match line {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => line,
+                Err(err) => {
+                    let _ = tx.send(OllamaEvent::Error(err.to_string()));
+                    completed = true;
+                    break;
+                }
+            };
+
+            match serde_json::from_str::<OllamaChunk>(&line) {
+                Ok(chunk) => {
+                    if !chunk.response.is_empty() {
+                        let _ = tx.send(OllamaEvent::Token(chunk.response));
+                    }
+                    if chunk.done {
+                        let _ = tx.send(OllamaEvent::Done);
+                        completed = true;
+                        break;
+                    }
+                }
+                Err(err) => {
+                    let _ = tx.send(OllamaEvent::Error(err.to_string()));
+                    completed = true;
+                    break;
+                }
+            }
+        }
+
+        // The stream ended (connection drop, proxy timeout, ...) without ever
+        // sending a final `"done": true` chunk. Without this, `poll_ollama`
+        // would never see `Done`/`Error` and the chat box would stay disabled.
+        if !completed {
+            let _ = tx.send(OllamaEvent::Error(
+                "Ollama closed the connection before sending a final response".to_owned(),
+            ));
+        }
+    });
+
+    rx
+}
+
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
@@ -46,6 +429,31 @@ pub struct AtlasApp {
     settings_window_open: bool,
     napkin_settings: NapkinSettings,
     napkin_temp_settings: NapkinSettings,
+    prompt_input: String,
+    #[serde(skip)]
+    conversation: Vec<ChatMessage>,
+    #[serde(skip)]
+    streaming_answer: String,
+    #[serde(skip)]
+    ollama_rx: Option<mpsc::Receiver<OllamaEvent>>,
+    #[serde(skip)]
+    ollama_busy: bool,
+    #[serde(skip)]
+    current_project: Project,
+    #[serde(skip)]
+    save_path: Option<PathBuf>,
+    expanded_dirs: HashSet<PathBuf>,
+    selected_file: Option<PathBuf>,
+    graph_view_open: bool,
+    layers_window_open: bool,
+    #[serde(skip)]
+    graph_temperature: f32,
+    #[serde(skip)]
+    graph_pan: egui::Vec2,
+    #[serde(skip)]
+    graph_zoom: f32,
+    #[serde(skip)]
+    selected_node: Option<usize>,
 }
 
 impl Default for AtlasApp {
@@ -59,6 +467,21 @@ impl Default for AtlasApp {
             settings_window_open: false,
             napkin_settings: NapkinSettings::default(),
             napkin_temp_settings: NapkinSettings::default(),
+            prompt_input: String::new(),
+            conversation: Vec::new(),
+            streaming_answer: String::new(),
+            ollama_rx: None,
+            ollama_busy: false,
+            current_project: Project::default(),
+            save_path: None,
+            expanded_dirs: HashSet::new(),
+            selected_file: None,
+            graph_view_open: false,
+            layers_window_open: false,
+            graph_temperature: 0.0,
+            graph_pan: egui::Vec2::ZERO,
+            graph_zoom: 1.0,
+            selected_node: None,
         }
     }
 }
@@ -85,6 +508,137 @@ impl AtlasApp {
     pub fn revert_settings(&mut self) {
         self.napkin_temp_settings = self.napkin_settings.clone();
     }
+
+    /// Picks a folder to index and starts a fresh, unsaved project rooted there.
+    pub fn new_project(&mut self) {
+        let Some(root) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+
+        self.current_project = Project::new(root);
+        self.save_path = None;
+        self.expanded_dirs.clear();
+        self.selected_file = None;
+        self.selected_node = None;
+        self.graph_temperature = INITIAL_GRAPH_TEMPERATURE;
+    }
+
+    /// Opens a project file, replacing `current_project` and `save_path` on success.
+    pub fn open_project(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Napkin project", &["napkin"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        match Project::load_from(&path) {
+            Ok(project) => {
+                self.current_project = project;
+                self.save_path = Some(path);
+                self.selected_node = None;
+                self.graph_temperature = INITIAL_GRAPH_TEMPERATURE;
+            }
+            Err(err) => {
+                self.streaming_answer = format!("[failed to open project: {err}]");
+            }
+        }
+    }
+
+    /// Saves to the existing `save_path`, falling back to a Save As dialog
+    /// when the project hasn't been saved before.
+    pub fn save_project(&mut self) {
+        match &self.save_path {
+            Some(path) => {
+                let _ = self.current_project.save_to(path);
+            }
+            None => self.save_project_as(),
+        }
+    }
+
+    pub fn save_project_as(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Napkin project", &["napkin"])
+            .save_file()
+        else {
+            return;
+        };
+
+        if self.current_project.save_to(&path).is_ok() {
+            self.save_path = Some(path);
+        }
+    }
+
+    /// Kicks off a streaming request for the text currently sitting in the
+    /// prompt box, if one isn't already in flight.
+    pub fn send_prompt(&mut self) {
+        if self.ollama_busy || self.prompt_input.trim().is_empty() {
+            return;
+        }
+
+        let prompt = std::mem::take(&mut self.prompt_input);
+        self.conversation.push(ChatMessage {
+            role: ChatRole::User,
+            content: prompt.clone(),
+        });
+
+        // A node selected in the graph view is attached as context: its file
+        // path is prepended so the model knows what the prompt is about.
+        let context_path = self
+            .selected_node
+            .and_then(|i| self.current_project.graph.nodes.get(i))
+            .map(|node| node.path.clone());
+        let prompt_with_context = match &context_path {
+            Some(path) => format!("[context: {}]\n{prompt}", path.display()),
+            None => prompt,
+        };
+
+        self.streaming_answer.clear();
+        self.ollama_busy = true;
+        self.ollama_rx = Some(spawn_ollama_request(
+            self.napkin_settings.service.clone(),
+            self.napkin_settings.model.clone(),
+            prompt_with_context,
+        ));
+    }
+
+    /// Drains whatever the background request thread has pushed so far and
+    /// asks for a repaint while a response is still streaming in.
+    pub fn poll_ollama(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.ollama_rx else {
+            return;
+        };
+
+        let mut done = false;
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                OllamaEvent::Token(token) => {
+                    self.streaming_answer.push_str(&token);
+                    ctx.request_repaint();
+                }
+                OllamaEvent::Done => {
+                    done = true;
+                }
+                OllamaEvent::Error(err) => {
+                    self.streaming_answer.push_str(&format!("\n[error: {err}]"));
+                    done = true;
+                }
+            }
+        }
+
+        if !done {
+            ctx.request_repaint();
+        }
+
+        if done {
+            self.conversation.push(ChatMessage {
+                role: ChatRole::Assistant,
+                content: std::mem::take(&mut self.streaming_answer),
+            });
+            self.ollama_busy = false;
+            self.ollama_rx = None;
+        }
+    }
 }
 
 impl eframe::App for AtlasApp {
@@ -113,6 +667,23 @@ impl eframe::App for AtlasApp {
                 let is_web = cfg!(target_arch = "wasm32");
                 if !is_web {
                     ui.menu_button("File", |ui| {
+                        if ui.button("New Project…").clicked() {
+                            self.new_project();
+                            ui.close_menu();
+                        }
+                        if ui.button("Open Project…").clicked() {
+                            self.open_project();
+                            ui.close_menu();
+                        }
+                        if ui.button("Save Project").clicked() {
+                            self.save_project();
+                            ui.close_menu();
+                        }
+                        if ui.button("Save Project As…").clicked() {
+                            self.save_project_as();
+                            ui.close_menu();
+                        }
+                        ui.separator();
                         if ui.button("Settings").clicked() {
                             self.settings_window_open = true;
                         }
@@ -145,6 +716,8 @@ impl eframe::App for AtlasApp {
                         }
                     });
                     ui.toggle_value(&mut self.side_panel_open, "File Browser");
+                    ui.toggle_value(&mut self.graph_view_open, "Graph");
+                    ui.toggle_value(&mut self.layers_window_open, "Layers");
                 });
             });
         });
@@ -156,7 +729,7 @@ impl eframe::App for AtlasApp {
                 ui.set_width(200.0);
                 ui.with_layout(
                     egui::Layout::top_down(Align::Min).with_cross_align(Align::Min),
-                    |ui| ui.heading("Side Panel"),
+                    |ui| file_browser_panel(ui, self),
                 );
             });
 
@@ -173,14 +746,21 @@ impl eframe::App for AtlasApp {
                 egui::warn_if_debug_build(ui);
             });
         });
+        self.poll_ollama(ctx);
         central_panel(ctx, self);
         settings_window(ctx, self);
+        layers_window(ctx, self);
     }
 }
 
 fn central_panel(ctx: &egui::Context, app: &mut AtlasApp) {
     egui::CentralPanel::default()
     .show(ctx, |ui| {
+    if app.graph_view_open {
+        graph_panel(ui, app);
+        return;
+    }
+
     // The central panel the region left after adding TopPanel's and SidePanel's
     // ui.heading("eframe template");
 
@@ -205,6 +785,9 @@ fn central_panel(ctx: &egui::Context, app: &mut AtlasApp) {
     //     "https://github.com/emilk/eframe_template/blob/master/",
     //     "Source code."
     // ));
+
+    ui.separator();
+    chat_panel(ui, app);
 });
 
     if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(Key::B)) {
@@ -212,6 +795,320 @@ fn central_panel(ctx: &egui::Context, app: &mut AtlasApp) {
     }
 }
 
+const INITIAL_GRAPH_TEMPERATURE: f32 = 60.0;
+/// Re-warm to at least this temperature (instead of a full reset) when a new file is indexed,
+/// so existing nodes only jostle a little to make room rather than re-melting.
+const GRAPH_TEMPERATURE_BUMP_ON_NEW_NODE: f32 = 20.0;
+const GRAPH_COOLING_RATE: f32 = 0.96;
+const GRAPH_LAYOUT_ITERATIONS_PER_FRAME: usize = 2;
+
+fn layer_color(layers: &[Layer], layer: usize, fallback: egui::Color32) -> egui::Color32 {
+    layers
+        .get(layer)
+        .map(|layer| egui::Color32::from_rgb(layer.color[0], layer.color[1], layer.color[2]))
+        .unwrap_or(fallback)
+}
+
+fn safe_normalized(v: egui::Vec2) -> egui::Vec2 {
+    if v.length() > 1e-6 {
+        v / v.length()
+    } else {
+        egui::Vec2::ZERO
+    }
+}
+
+/// Spreads any node still sitting at the origin (freshly added, never laid
+/// out) around the panel's center so the force layout has something to push
+/// apart instead of starting from a single point.
+fn scatter_initial_positions(graph: &mut CodeGraph, rect: egui::Rect) {
+    let center = rect.center();
+    let radius = rect.width().min(rect.height()) * 0.25;
+
+    for (i, node) in graph.nodes.iter_mut().enumerate() {
+        if node.x == 0.0 && node.y == 0.0 {
+            let angle = i as f32 * 2.399_963; // golden angle: spreads points evenly
+            node.x = center.x + radius * angle.cos();
+            node.y = center.y + radius * angle.sin();
+        }
+    }
+}
+
+/// One Fruchterman-Reingold step: nodes repel each other with a force
+/// proportional to `k^2/d`, edges pull their endpoints together with a force
+/// proportional to `d^2/k`, and the total displacement per node is capped by
+/// `temperature`, which is multiplied by `GRAPH_COOLING_RATE` at the end of
+/// each call that runs the layout.
+fn step_force_layout(graph: &mut CodeGraph, rect: egui::Rect, temperature: &mut f32) {
+    if graph.nodes.len() < 2 || *temperature <= 0.5 {
+        return;
+    }
+
+    let area = rect.width() * rect.height();
+    let k = (area / graph.nodes.len() as f32).sqrt();
+
+    for _ in 0..GRAPH_LAYOUT_ITERATIONS_PER_FRAME {
+        let mut displacement = vec![egui::Vec2::ZERO; graph.nodes.len()];
+
+        for (i, node_i) in graph.nodes.iter().enumerate() {
+            for j in 0..graph.nodes.len() {
+                if i == j {
+                    continue;
+                }
+                let delta = egui::vec2(node_i.x - graph.nodes[j].x, node_i.y - graph.nodes[j].y);
+                let distance = delta.length().max(0.01);
+                let repulsion = k * k / distance;
+                displacement[i] += safe_normalized(delta) * repulsion;
+            }
+        }
+
+        for edge in &graph.edges {
+            if edge.from == edge.to || edge.from >= graph.nodes.len() || edge.to >= graph.nodes.len() {
+                continue;
+            }
+            let delta = egui::vec2(
+                graph.nodes[edge.from].x - graph.nodes[edge.to].x,
+                graph.nodes[edge.from].y - graph.nodes[edge.to].y,
+            );
+            let distance = delta.length().max(0.01);
+            let attraction = safe_normalized(delta) * (distance * distance / k);
+            displacement[edge.from] -= attraction;
+            displacement[edge.to] += attraction;
+        }
+
+        for (node, disp) in graph.nodes.iter_mut().zip(displacement) {
+            let capped = safe_normalized(disp) * disp.length().min(*temperature);
+            node.x = (node.x + capped.x).clamp(rect.left(), rect.right());
+            node.y = (node.y + capped.y).clamp(rect.top(), rect.bottom());
+        }
+    }
+
+    *temperature *= GRAPH_COOLING_RATE;
+}
+
+fn graph_panel(ui: &mut egui::Ui, app: &mut AtlasApp) {
+    let rect = ui.max_rect();
+    let (response, painter) = ui.allocate_painter(rect.size(), egui::Sense::click_and_drag());
+
+    scatter_initial_positions(&mut app.current_project.graph, rect);
+    step_force_layout(&mut app.current_project.graph, rect, &mut app.graph_temperature);
+    if app.graph_temperature > 0.5 {
+        ui.ctx().request_repaint();
+    }
+
+    if response.dragged() {
+        app.graph_pan += response.drag_delta();
+    }
+    let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+    if scroll != 0.0 {
+        app.graph_zoom = (app.graph_zoom * (1.0 + scroll * 0.001)).clamp(0.2, 4.0);
+    }
+
+    let pan = app.graph_pan;
+    let zoom = app.graph_zoom;
+    let to_screen = |p: egui::Pos2| rect.center() + pan + (p - rect.center()) * zoom;
+
+    let layers = &app.current_project.layers;
+    let is_layer_visible = |layer: usize| layers.get(layer).is_none_or(|layer| layer.visible);
+
+    for edge in &app.current_project.graph.edges {
+        if !is_layer_visible(edge.layer) {
+            continue;
+        }
+        let (Some(from), Some(to)) = (
+            app.current_project.graph.nodes.get(edge.from),
+            app.current_project.graph.nodes.get(edge.to),
+        ) else {
+            continue;
+        };
+        painter.line_segment(
+            [
+                to_screen(egui::pos2(from.x, from.y)),
+                to_screen(egui::pos2(to.x, to.y)),
+            ],
+            egui::Stroke::new(1.0, ui.visuals().weak_text_color()),
+        );
+    }
+
+    let pointer = response.interact_pointer_pos();
+    let mut clicked_node = None;
+
+    for (i, node) in app.current_project.graph.nodes.iter().enumerate() {
+        if !is_layer_visible(node.layer) {
+            continue;
+        }
+
+        let center = to_screen(egui::pos2(node.x, node.y));
+        let radius = 6.0 * zoom;
+        let color = if app.selected_node == Some(i) {
+            ui.visuals().selection.bg_fill
+        } else {
+            layer_color(layers, node.layer, ui.visuals().text_color())
+        };
+
+        painter.circle_filled(center, radius, color);
+        painter.text(
+            center + egui::vec2(radius + 2.0, 0.0),
+            egui::Align2::LEFT_CENTER,
+            &node.label,
+            egui::FontId::default(),
+            ui.visuals().text_color(),
+        );
+
+        if response.clicked() && pointer.is_some_and(|p| p.distance(center) <= radius + 2.0) {
+            clicked_node = Some(i);
+        }
+    }
+
+    if clicked_node.is_some() {
+        app.selected_node = clicked_node;
+    }
+}
+
+fn chat_panel(ui: &mut egui::Ui, app: &mut AtlasApp) {
+    ui.heading("Chat");
+
+    egui::ScrollArea::vertical()
+        .max_height(240.0)
+        .show(ui, |ui| {
+            for message in &app.conversation {
+                let who = match message.role {
+                    ChatRole::User => "You",
+                    ChatRole::Assistant => &app.napkin_settings.model,
+                };
+                ui.label(format!("{who}: {}", message.content));
+            }
+            if app.ollama_busy {
+                ui.label(format!("{}: {}", app.napkin_settings.model, app.streaming_answer));
+            }
+        });
+
+    ui.separator();
+
+    if let Some(node) = app
+        .selected_node
+        .and_then(|i| app.current_project.graph.nodes.get(i))
+    {
+        ui.label(format!("Context: {}", node.path.display()));
+    }
+
+    ui.horizontal(|ui| {
+        let response = ui.add_enabled(
+            !app.ollama_busy,
+            egui::TextEdit::singleline(&mut app.prompt_input).hint_text("Ask something…"),
+        );
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+        let clicked = ui
+            .add_enabled(!app.ollama_busy, egui::Button::new("Send"))
+            .clicked();
+
+        if submitted || clicked {
+            app.send_prompt();
+        }
+    });
+}
+
+fn file_browser_panel(ui: &mut egui::Ui, app: &mut AtlasApp) {
+    ui.heading("File Browser");
+    ui.separator();
+
+    if app.current_project.file_tree.is_none() {
+        ui.label("No project open.");
+        return;
+    }
+
+    let indexed_before = app.current_project.indexed_files.len();
+
+    // Borrow each field we need separately (rather than cloning the whole
+    // tree, or letting the closure below reach through `app.current_project`
+    // and `app` field-by-field) so this stays cheap on every repaint.
+    let children = &app.current_project.file_tree.as_ref().unwrap().children;
+    let layers = &app.current_project.layers;
+    let expanded_dirs = &mut app.expanded_dirs;
+    let selected_file = &mut app.selected_file;
+    let indexed_files = &mut app.current_project.indexed_files;
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for child in children {
+            render_file_node(ui, child, layers, expanded_dirs, selected_file, indexed_files);
+        }
+    });
+
+    if app.current_project.indexed_files.len() != indexed_before {
+        app.current_project.rebuild_graph();
+        app.graph_temperature = app.graph_temperature.max(GRAPH_TEMPERATURE_BUMP_ON_NEW_NODE);
+    }
+}
+
+fn render_file_node(
+    ui: &mut egui::Ui,
+    node: &FileNode,
+    layers: &[Layer],
+    expanded_dirs: &mut HashSet<PathBuf>,
+    selected_file: &mut Option<PathBuf>,
+    indexed_files: &mut Vec<PathBuf>,
+) {
+    let layer_visible = layers.get(node.layer).is_none_or(|layer| layer.visible);
+    if !node.is_dir && !layer_visible {
+        return;
+    }
+
+    let name = node
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| node.path.display().to_string());
+
+    if node.is_dir {
+        let is_open = expanded_dirs.contains(&node.path);
+        let header = egui::CollapsingHeader::new(name)
+            .id_salt(&node.path)
+            .open(Some(is_open))
+            .show(ui, |ui| {
+                for child in &node.children {
+                    render_file_node(
+                        ui,
+                        child,
+                        layers,
+                        expanded_dirs,
+                        selected_file,
+                        indexed_files,
+                    );
+                }
+            });
+
+        if header.header_response.clicked() {
+            if is_open {
+                expanded_dirs.remove(&node.path);
+            } else {
+                expanded_dirs.insert(node.path.clone());
+            }
+        }
+    } else {
+        let is_selected = selected_file.as_deref() == Some(node.path.as_path());
+        if ui.selectable_label(is_selected, name).clicked() {
+            *selected_file = Some(node.path.clone());
+            if !indexed_files.contains(&node.path) {
+                indexed_files.push(node.path.clone());
+            }
+        }
+    }
+}
+
+fn layers_window(ctx: &egui::Context, app: &mut AtlasApp) {
+    egui::Window::new("Layers")
+        .open(&mut app.layers_window_open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            for layer in &mut app.current_project.layers {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut layer.visible, "");
+                    ui.color_edit_button_srgb(&mut layer.color);
+                    ui.label(&layer.name);
+                });
+            }
+        });
+}
+
 fn settings_window(ctx: &egui::Context, app: &mut AtlasApp) {
     let mut should_close = false;
     let mut should_save = false;
@@ -257,4 +1154,95 @@ fn settings_window(ctx: &egui::Context, app: &mut AtlasApp) {
             app.revert_settings();
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_round_trips_through_save_and_load() {
+        let path = std::env::temp_dir().join(format!("napkin-project-test-{}.json", std::process::id()));
+
+        let mut project = Project::new(PathBuf::from("."));
+        project.indexed_files.push(PathBuf::from("src/main.rs"));
+        project.graph.nodes.push(GraphNode {
+            label: "main.rs".to_owned(),
+            kind: NodeKind::File,
+            path: PathBuf::from("src/main.rs"),
+            layer: LAYER_SOURCE_FILES,
+            x: 12.0,
+            y: -4.0,
+        });
+
+        project.save_to(&path).expect("save_to should succeed");
+        let loaded = Project::load_from(&path).expect("load_from should succeed");
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.indexed_files, project.indexed_files);
+        assert_eq!(loaded.graph.nodes.len(), 1);
+        assert_eq!(loaded.graph.nodes[0].path, PathBuf::from("src/main.rs"));
+        assert_eq!(loaded.graph.nodes[0].x, 12.0);
+        assert_eq!(loaded.graph.nodes[0].y, -4.0);
+    }
+
+    #[test]
+    fn project_loads_graph_nodes_saved_before_the_path_field_existed() {
+        let path = std::env::temp_dir().join(format!("napkin-legacy-project-test-{}.json", std::process::id()));
+        let legacy_json = r#"{
+            "root": null,
+            "indexed_files": [],
+            "graph": {
+                "nodes": [
+                    { "label": "main.rs", "kind": "File", "layer": 0, "x": 1.0, "y": 2.0 }
+                ],
+                "edges": []
+            },
+            "layers": []
+        }"#;
+        fs::write(&path, legacy_json).unwrap();
+
+        let loaded = Project::load_from(&path).expect("legacy project file should still load");
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.graph.nodes.len(), 1);
+        assert_eq!(loaded.graph.nodes[0].path, PathBuf::new());
+    }
+
+    #[test]
+    fn gitignore_excludes_combines_defaults_with_plain_entries() {
+        let dir = std::env::temp_dir().join(format!("napkin-gitignore-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(".gitignore"), "dist\n# comment\n\n*.log\nsrc/generated\nbuild/\n").unwrap();
+
+        let excludes = gitignore_excludes(&dir);
+        fs::remove_dir_all(&dir).ok();
+
+        // Always-on excludes are present regardless of .gitignore contents.
+        assert!(excludes.contains("target"));
+        assert!(excludes.contains(".git"));
+        assert!(excludes.contains("node_modules"));
+        // A bare name is picked up...
+        assert!(excludes.contains("dist"));
+        // ...a trailing slash is stripped...
+        assert!(excludes.contains("build"));
+        // ...but comments, globs, and nested paths are not picked up.
+        assert!(!excludes.contains("*.log"));
+        assert!(!excludes.contains("src/generated"));
+    }
+
+    #[test]
+    fn guess_layer_for_path_buckets_by_path_component() {
+        let cases = [
+            (PathBuf::from("src/app.rs"), LAYER_SOURCE_FILES),
+            (PathBuf::from("src/tests/app_test.rs"), LAYER_TESTS),
+            (PathBuf::from("crates/core/test/fixture.rs"), LAYER_TESTS),
+            (PathBuf::from("crates/widget_tests/mod.rs"), LAYER_TESTS),
+            (PathBuf::from("apps/atlas/src/theme.rs"), LAYER_SOURCE_FILES),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(guess_layer_for_path(&path), expected, "{path:?}");
+        }
+    }
 }
\ No newline at end of file